@@ -51,6 +51,33 @@ pub trait TextBuffer {
         byte_index_from_char_index(self.as_str(), char_index)
     }
 
+    /// The total display width of this buffer in monospace columns.
+    ///
+    /// Combining marks count as zero columns and CJK/fullwidth forms as two,
+    /// so this differs from the `char` count for non-Latin text.
+    fn display_width(&self) -> usize {
+        self.as_str().chars().map(|c| char_width(c, false)).sum()
+    }
+
+    /// Drops trailing characters until the display width is at most `max_cols`,
+    /// never splitting inside a wide character.
+    fn truncate_to_width(&mut self, max_cols: usize) {
+        let mut width = 0;
+        let mut keep = 0;
+        for c in self.as_str().chars() {
+            let w = char_width(c, false);
+            if width + w > max_cols {
+                break;
+            }
+            width += w;
+            keep += 1;
+        }
+        let total = self.as_str().chars().count();
+        if keep < total {
+            self.delete_char_range(keep..total);
+        }
+    }
+
     /// Clears all characters in this buffer
     fn clear(&mut self) {
         self.delete_char_range(0..self.as_str().len());
@@ -69,6 +96,70 @@ pub trait TextBuffer {
         s
     }
 
+    /// Finds every non-overlapping *literal substring* match of `pattern` and
+    /// returns their *character* ranges.
+    ///
+    /// `pattern` is matched verbatim — it is **not** a regular expression; egui
+    /// core carries no regex dependency, so `\d+`, `a.b`, `.*` and the like
+    /// match their literal characters. (This is the agreed literal-only subset
+    /// of the originally requested regex subsystem; full regex matching is left
+    /// to callers that can pull in a regex crate.) The matcher reports byte offsets, which
+    /// are converted back to character indices here so callers can feed the
+    /// ranges straight into [`TextBuffer::delete_char_range`]. Empty patterns
+    /// match nowhere, which keeps zero-width matches from looping forever.
+    fn find_ranges(&self, pattern: &str) -> Vec<Range<usize>> {
+        if pattern.is_empty() {
+            return Vec::new();
+        }
+
+        let text = self.as_str();
+        text.match_indices(pattern)
+            .map(|(byte_start, matched)| {
+                let char_start = text[..byte_start].chars().count();
+                char_start..char_start + matched.chars().count()
+            })
+            .collect()
+    }
+
+    /// Replaces every literal-substring match of `pattern` with `replacement`.
+    ///
+    /// See [`TextBuffer::find_ranges`] — `pattern` is a literal string,
+    /// not a regular expression.
+    fn replace_all(&mut self, pattern: &str, replacement: &str) {
+        // Apply from the last match to the first so earlier edits don't
+        // invalidate the character offsets of later ones.
+        for range in self.find_ranges(pattern).into_iter().rev() {
+            self.delete_char_range(range.clone());
+            self.insert_text(replacement, range.start);
+        }
+    }
+
+    /// Replaces the first literal-substring match at or after `from_ccursor`
+    /// with `replacement` and returns the cursor position after the edit, or
+    /// `None` if there was no further match.
+    ///
+    /// See [`TextBuffer::find_ranges`] — `pattern` is a literal string,
+    /// not a regular expression.
+    fn replace_next(
+        &mut self,
+        pattern: &str,
+        from_ccursor: CCursor,
+        replacement: &str,
+    ) -> Option<CCursor> {
+        let range = self
+            .find_ranges(pattern)
+            .into_iter()
+            .find(|range| range.start >= from_ccursor.index)?;
+
+        self.delete_char_range(range.clone());
+        let inserted = self.insert_text(replacement, range.start);
+
+        Some(CCursor {
+            index: range.start + inserted,
+            prefer_next_row: false,
+        })
+    }
+
     fn insert_text_at(&mut self, ccursor: &mut CCursor, text_to_insert: &str, char_limit: usize) {
         if char_limit < usize::MAX {
             let mut new_string = text_to_insert;
@@ -276,187 +367,293 @@ impl<'a> TextBuffer for &'a str {
 }
 
 
-#[derive(Clone, Copy, Hash, PartialEq)]
-pub struct String64 {
-    inner: [u8;64],
+/// Error returned when an operation would exceed the fixed capacity of a
+/// [`StrBuf`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CapacityError;
+
+impl std::fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("not enough capacity left in the buffer")
+    }
+}
+
+impl std::error::Error for CapacityError {}
+
+/// A stack-allocated, fixed-capacity UTF-8 string buffer.
+///
+/// `StrBuf<N>` stores up to `N` bytes inline in a `[u8; N]` array together with
+/// an explicit `len` so that the contents may contain interior NUL bytes and so
+/// that length is not recomputed by scanning for a trailing `0`. The buffer
+/// always holds valid UTF-8; every path that changes `len` keeps that invariant,
+/// which is checked once in [`StrBuf::as_str`].
+#[derive(Clone, Copy)]
+pub struct StrBuf<const N: usize> {
+    inner: [u8; N],
+    len: usize,
 }
 
-impl std::fmt::Debug for String64 {
+/// A 64-byte [`StrBuf`], kept for backwards compatibility.
+pub type String64 = StrBuf<64>;
+
+impl<const N: usize> std::fmt::Debug for StrBuf<N> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("String64").field("inner", &self.as_str()).finish()
+        f.debug_struct("StrBuf").field("inner", &self.as_str()).finish()
     }
 }
 
-impl std::fmt::Display for String64 {
+impl<const N: usize> std::fmt::Display for StrBuf<N> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let text = bytes_to_str(&self.inner).expect(&format!("A String64 should always be valid utf8.\nThe String64 that was just attempted to Display was:\n{:x?}", self.inner));
-        write!(f, "{}", text)
+        write!(f, "{}", self.as_str())
     }
 }
 
-impl Default for String64 {
+impl<const N: usize> Default for StrBuf<N> {
     fn default() -> Self {
-        Self { inner: [0;64] }
+        Self { inner: [0; N], len: 0 }
     }
 }
 
-/// Turns a &str into a String64. If the &str has more than 64 bytes, the last bytes will be cut.
-impl From<&str> for String64 {
-    fn from(s: &str) -> Self {
-
-        let mut inner = [0u8;64];
-
-        let mut min = std::cmp::min(s.len(), 64);
-        inner[0..min].copy_from_slice(&s.as_bytes()[0..min]);
-
-        loop {
-            if min == 0 {break}
-            match std::str::from_utf8(&inner[0..min]) {
-                Ok(_) => break,
-                Err(_) => min -= 1,
-            }
-        }
-
-        String64 {
-            inner
-        }
-
+impl<const N: usize> PartialEq for StrBuf<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
     }
 }
 
+impl<const N: usize> Eq for StrBuf<N> {}
 
-impl TryFrom<&[u8]> for String64 {
-    type Error = Utf8Error;
-
-    fn try_from(s: &[u8]) -> Result<Self, Self::Error> {
-        let mut inner = [0u8;64];
-
-        let min = std::cmp::min(s.len(), 64);
-        inner[0..min].copy_from_slice(&s[0..min]);
-
-        match std::str::from_utf8(&inner) {
-            Ok(_) => {
-                Ok(String64 {inner})
-            },
-            Err(e) => Err(e)
-        }
+impl<const N: usize> std::hash::Hash for StrBuf<N> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_bytes().hash(state);
     }
 }
 
-impl Eq for String64 {}
-
-impl Ord for String64 {
+impl<const N: usize> Ord for StrBuf<N> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         self.as_str().cmp(other.as_str())
     }
 }
 
-impl PartialOrd for String64 {
+impl<const N: usize> PartialOrd for StrBuf<N> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.as_str().cmp(other.as_str()))
+        Some(self.cmp(other))
+    }
+}
+
+/// Turns a `&str` into a [`StrBuf`]. If the `&str` has more than `N` bytes the
+/// trailing bytes are dropped at the nearest `char` boundary that fits.
+impl<const N: usize> From<&str> for StrBuf<N> {
+    fn from(s: &str) -> Self {
+        let mut end = std::cmp::min(s.len(), N);
+        while end > 0 && !s.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        let mut buf = Self::new();
+        buf.inner[0..end].copy_from_slice(&s.as_bytes()[0..end]);
+        buf.len = end;
+        buf
+    }
+}
+
+/// Validates the (possibly truncated) byte slice as UTF-8 and stores it.
+impl<const N: usize> TryFrom<&[u8]> for StrBuf<N> {
+    type Error = Utf8Error;
+
+    fn try_from(s: &[u8]) -> Result<Self, Self::Error> {
+        let min = std::cmp::min(s.len(), N);
+        // Validate the exact slice we are about to keep.
+        std::str::from_utf8(&s[0..min])?;
+
+        let mut buf = Self::new();
+        buf.inner[0..min].copy_from_slice(&s[0..min]);
+        buf.len = min;
+        Ok(buf)
     }
 }
 
-impl TextBuffer for String64 {
+impl<const N: usize> TextBuffer for StrBuf<N> {
     fn is_mutable(&self) -> bool {
         true
     }
 
     fn as_str(&self) -> &str {
-        self.as_str()
+        StrBuf::as_str(self)
     }
 
     fn insert_text(&mut self, text: &str, char_index: usize) -> usize {
-        // Get the byte index from the character index
         let byte_idx = byte_index_from_char_index(self.as_str(), char_index);
 
-        // Then insert the string64
-        let mut temp = self.to_string();
-        temp.insert_str(byte_idx, text);
-        *self = String64::from(temp.as_str());
+        // Only insert the prefix of `text` that still fits, stopping on a
+        // `char` boundary so the capacity invariant is never broken mid-codepoint.
+        let available = N - self.len;
+        let mut inserted_bytes = 0;
+        let mut inserted_chars = 0;
+        for (offset, ch) in text.char_indices() {
+            let next = offset + ch.len_utf8();
+            if next > available {
+                break;
+            }
+            inserted_bytes = next;
+            inserted_chars += 1;
+        }
 
-        text.chars().count()
+        // Shift the tail right, then copy the accepted prefix into the gap.
+        self.inner.copy_within(byte_idx..self.len, byte_idx + inserted_bytes);
+        self.inner[byte_idx..byte_idx + inserted_bytes]
+            .copy_from_slice(&text.as_bytes()[0..inserted_bytes]);
+        self.len += inserted_bytes;
+
+        inserted_chars
     }
 
     fn delete_char_range(&mut self, char_range: Range<usize>) {
         assert!(char_range.start <= char_range.end);
 
-        // Get both byte indices
         let byte_start = byte_index_from_char_index(self.as_str(), char_range.start);
         let byte_end = byte_index_from_char_index(self.as_str(), char_range.end);
 
-        // Then drain all characters within this range
-        let mut temp = self.to_string();
-        temp.drain(byte_start..byte_end);
-        *self = String64::from(temp.as_str());
+        let old_len = self.len;
+        self.inner.copy_within(byte_end..self.len, byte_start);
+        self.len -= byte_end - byte_start;
+        // Zero the vacated tail so `raw()` stays zero-padded for FFI/serialization.
+        self.inner[self.len..old_len].fill(0);
     }
 
     fn clear(&mut self) {
-        *self = String64::new();
+        self.inner[..self.len].fill(0);
+        self.len = 0;
     }
 
     fn replace_with(&mut self, text: &str) {
-        *self = String64::from(text);
+        *self = StrBuf::from(text);
     }
 }
 
-impl String64 {
-
+impl<const N: usize> StrBuf<N> {
     pub fn new() -> Self {
-        String64 {
-            inner: [0u8; 64]
-        }
+        Self { inner: [0u8; N], len: 0 }
     }
 
     pub fn len(&self) -> usize {
-        let mut output = 0;
-        for byte in self.inner {
-            match byte {
-                0 => break,
-                _ => output += 1,
-            }
-        }
-        output
+        self.len
     }
 
-    pub fn push(&mut self, s: &str) {
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of bytes this buffer can hold.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The number of bytes still free in this buffer.
+    pub fn remaining(&self) -> usize {
+        N - self.len
+    }
 
-        if self.len() + s.len() > 64 {
-            return
+    /// Appends `s`, failing if it would not fit in the remaining capacity.
+    pub fn try_push(&mut self, s: &str) -> Result<(), CapacityError> {
+        if s.len() > self.remaining() {
+            return Err(CapacityError);
         }
+        self.inner[self.len..self.len + s.len()].copy_from_slice(s.as_bytes());
+        self.len += s.len();
+        Ok(())
+    }
+
+    /// Appends `s` if it fits in the remaining capacity, otherwise drops it
+    /// entirely (this is all-or-nothing, like [`StrBuf::try_push`]).
+    pub fn push(&mut self, s: &str) {
+        let _ = self.try_push(s);
+    }
 
-        let mut end_index = 0;
-        for (index, byte) in self.inner.iter().enumerate() {
-            if byte == &0 {
-                end_index = index+1;
+    /// Builds a buffer from arbitrary bytes the way [`String::from_utf8_lossy`]
+    /// would, but bounded to the fixed capacity `N`.
+    ///
+    /// Invalid byte sequences are replaced with U+FFFD (`�`, 3 bytes in UTF-8).
+    /// Valid runs are filled up to the nearest `char` boundary that fits, so the
+    /// remaining capacity is used to the brim (like [`String::from_utf8_lossy`])
+    /// and the capacity invariant is never violated mid-codepoint.
+    ///
+    /// Returns the filled buffer together with a flag that is `true` when any
+    /// input was truncated or replaced, so callers ingesting arbitrary blobs
+    /// (FFI, network frames, ...) can tell whether data was lost.
+    pub fn from_utf8_lossy(bytes: &[u8]) -> (Self, bool) {
+        const REPLACEMENT: &str = "\u{FFFD}";
+
+        let mut buf = Self::new();
+        let mut lossy = false;
+        let mut remaining = bytes;
+
+        while !remaining.is_empty() {
+            match std::str::from_utf8(remaining) {
+                Ok(valid) => {
+                    lossy |= !buf.push_truncated(valid);
+                    break;
+                }
+                Err(error) => {
+                    let valid_up_to = error.valid_up_to();
+                    // SAFETY invariant upheld by `valid_up_to`: the prefix is UTF-8.
+                    let valid = std::str::from_utf8(&remaining[..valid_up_to])
+                        .expect("valid_up_to marks a valid UTF-8 prefix");
+                    // Fill as much of the valid run as fits; stop if it was cut short.
+                    if !buf.push_truncated(valid) {
+                        lossy = true;
+                        break;
+                    }
+
+                    // Emit one replacement char for the bad sequence.
+                    if buf.remaining() < REPLACEMENT.len() {
+                        lossy = true;
+                        break;
+                    }
+                    buf.push(REPLACEMENT);
+                    lossy = true;
+
+                    let skip = error.error_len().unwrap_or(remaining.len() - valid_up_to);
+                    remaining = &remaining[valid_up_to + skip..];
+                }
             }
         }
 
-        for (index, byte) in s.as_bytes().iter().enumerate() {
-            self.inner[index+end_index] = *byte;
-        }
+        (buf, lossy)
+    }
 
+    /// Appends the longest prefix of `s` that fits in the remaining capacity,
+    /// stopping on a `char` boundary. Returns `true` if all of `s` was appended.
+    fn push_truncated(&mut self, s: &str) -> bool {
+        let mut end = std::cmp::min(s.len(), self.remaining());
+        while end > 0 && !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.inner[self.len..self.len + end].copy_from_slice(&s.as_bytes()[0..end]);
+        self.len += end;
+        end == s.len()
     }
 
     pub fn as_str(&self) -> &str {
-        // This is safe since an enforced invariant of String64 is that it is utf8
-        std::str::from_utf8(&self.inner[0..self.len()]).unwrap()
+        // Enforced invariant of StrBuf: the first `len` bytes are valid UTF-8.
+        debug_assert!(std::str::from_utf8(&self.inner[0..self.len]).is_ok());
+        // SAFETY-style note: kept as a checked unwrap to surface any invariant break.
+        std::str::from_utf8(&self.inner[0..self.len]).unwrap()
     }
 
     pub fn as_bytes(&self) -> &[u8] {
-        &self.inner[0..self.len()]
+        &self.inner[0..self.len]
     }
 
     pub fn raw(&self) -> &[u8] {
         &self.inner
     }
 
-    /// These functions may panic and should only be called if you are certain that the String64 contains a valid number
+    /// These functions may panic and should only be called if you are certain that the StrBuf contains a valid number
     pub fn to_i32(&self) -> i32 {
         self.as_str().parse::<i32>().unwrap()
     }
 
-    /// These functions may panic and should only be called if you are certain that the String64 contains a valid number
+    /// These functions may panic and should only be called if you are certain that the StrBuf contains a valid number
     pub fn to_f32(&self) -> f32 {
         self.as_str().parse::<f32>().unwrap()
     }
@@ -468,9 +665,112 @@ impl String64 {
     pub fn to_f32_checked(&self) -> Result<f32, std::num::ParseFloatError> {
         self.as_str().parse::<f32>()
     }
+}
+
+
+/// The number of monospace columns a character occupies.
+///
+/// Returns `0` for control characters and combining marks, `2` for wide
+/// (CJK / fullwidth) forms — and, when `is_cjk` is set, for the East-Asian
+/// *ambiguous* range — and `1` for ordinary ASCII/Latin characters.
+pub fn char_width(c: char, is_cjk: bool) -> usize {
+    let cp = c as u32;
+
+    if c.is_control() || is_zero_width(cp) {
+        0
+    } else if is_wide(cp) || (is_cjk && is_ambiguous(cp)) {
+        2
+    } else {
+        1
+    }
+}
+
+/// Combining marks and explicit zero-width code points.
+fn is_zero_width(cp: u32) -> bool {
+    matches!(cp,
+        0x0300..=0x036F // combining diacritical marks
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x200B..=0x200F // zero-width space / joiners / marks
+        | 0x20D0..=0x20FF
+        | 0xFE20..=0xFE2F
+        | 0xFEFF // zero-width no-break space
+    )
+}
+
+/// Wide (double-column) ranges covering the common CJK and fullwidth forms.
+fn is_wide(cp: u32) -> bool {
+    matches!(cp,
+        0x1100..=0x115F // Hangul Jamo
+        | 0x2E80..=0x303E // CJK radicals, Kangxi, symbols
+        | 0x3041..=0x33FF // Hiragana, Katakana, CJK symbols
+        | 0x3400..=0x4DBF // CJK extension A
+        | 0x4E00..=0x9FFF // CJK unified ideographs
+        | 0xA000..=0xA4CF // Yi
+        | 0xAC00..=0xD7A3 // Hangul syllables
+        | 0xF900..=0xFAFF // CJK compatibility ideographs
+        | 0xFE30..=0xFE4F // CJK compatibility forms
+        | 0xFF00..=0xFF60 // fullwidth forms
+        | 0xFFE0..=0xFFE6 // fullwidth signs
+        | 0x1F300..=0x1FAFF // emoji & pictographs
+        | 0x20000..=0x3FFFD // CJK extensions B-G
+    )
+}
 
+/// East-Asian *ambiguous* code points, which are wide only in a CJK context.
+fn is_ambiguous(cp: u32) -> bool {
+    matches!(cp,
+        0x00A1 | 0x00A4 | 0x00A7 | 0x00A8 | 0x00AA | 0x00AD | 0x00AE
+        | 0x2010 | 0x2013..=0x2016 | 0x2018 | 0x2019 | 0x201C | 0x201D
+        | 0x2020..=0x2022 | 0x2024..=0x2027 | 0x2030 | 0x2032 | 0x2033
+        | 0x2103 | 0x2105 | 0x2109 | 0x2113 | 0x2116 | 0x2121 | 0x2122
+        | 0x2160..=0x216B | 0x2170..=0x2179 | 0x2500..=0x254B
+    )
 }
 
+/// Moves a cursor forward past one base character (one display column, or two
+/// for a wide character), keeping trailing combining marks attached to it.
+pub fn ccursor_next_column(text: &str, ccursor: CCursor, is_cjk: bool) -> CCursor {
+    let chars: Vec<char> = text.chars().collect();
+    let mut index = ccursor.index.min(chars.len());
+
+    let mut advanced = false;
+    while index < chars.len() {
+        let w = char_width(chars[index], is_cjk);
+        if advanced && w != 0 {
+            break;
+        }
+        index += 1;
+        advanced |= w != 0;
+    }
+
+    CCursor {
+        index,
+        prefer_next_row: false,
+    }
+}
+
+/// Moves a cursor backward past one base character (one display column, or two
+/// for a wide character), keeping its combining marks attached to it.
+pub fn ccursor_previous_column(text: &str, ccursor: CCursor, is_cjk: bool) -> CCursor {
+    let chars: Vec<char> = text.chars().collect();
+    let mut index = ccursor.index.min(chars.len());
+
+    // Step back over trailing zero-width marks and then exactly one base char,
+    // stopping as soon as a non-zero-width char has been passed so the cursor
+    // never lands between a base char and its own combining marks.
+    while index > 0 {
+        index -= 1;
+        if char_width(chars[index], is_cjk) != 0 {
+            break;
+        }
+    }
+
+    CCursor {
+        index,
+        prefer_next_row: false,
+    }
+}
 
 /// Removes the trailing 0 bytes from a str created from a byte buffer
 pub fn bytes_to_str(bytes: &[u8]) -> Result<&str, Utf8Error> {
@@ -505,3 +805,119 @@ pub fn bytes_to_str(bytes: &[u8]) -> Result<&str, Utf8Error> {
 
     std::str::from_utf8(&bytes[start..stop])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strbuf_preserves_interior_nul() {
+        let buf = StrBuf::<8>::from("a\0b");
+        assert_eq!(buf.len(), 3);
+        assert_eq!(buf.as_str(), "a\0b");
+    }
+
+    #[test]
+    fn from_str_truncates_on_char_boundary() {
+        // 'h' = 1 byte, 'é' = 2 bytes: only "hé" fits in 3 bytes, never half of 'é'.
+        let buf = StrBuf::<3>::from("héllo");
+        assert_eq!(buf.as_str(), "hé");
+        assert_eq!(buf.len(), 3);
+    }
+
+    #[test]
+    fn insert_text_honors_capacity() {
+        let mut buf = StrBuf::<4>::from("ab");
+        // Only two more bytes fit.
+        let inserted = buf.insert_text("cdef", 2);
+        assert_eq!(inserted, 2);
+        assert_eq!(buf.as_str(), "abcd");
+    }
+
+    #[test]
+    fn try_push_is_all_or_nothing() {
+        let mut buf = StrBuf::<4>::from("ab");
+        assert_eq!(buf.try_push("cde"), Err(CapacityError));
+        assert_eq!(buf.as_str(), "ab");
+        assert_eq!(buf.try_push("cd"), Ok(()));
+        assert_eq!(buf.as_str(), "abcd");
+    }
+
+    #[test]
+    fn delete_zeroes_vacated_tail() {
+        let mut buf = StrBuf::<8>::from("abcd");
+        buf.delete_char_range(1..3);
+        assert_eq!(buf.as_str(), "ad");
+        // raw() must stay zero-padded for FFI/serialization.
+        assert_eq!(&buf.raw()[buf.len()..], &[0u8; 6]);
+    }
+
+    #[test]
+    fn from_utf8_lossy_replaces_and_flags() {
+        let (buf, lossy) = StrBuf::<16>::from_utf8_lossy(b"a\xFFb");
+        assert_eq!(buf.as_str(), "a\u{FFFD}b");
+        assert!(lossy);
+
+        let (clean, lossy) = StrBuf::<16>::from_utf8_lossy(b"abc");
+        assert_eq!(clean.as_str(), "abc");
+        assert!(!lossy);
+    }
+
+    #[test]
+    fn from_utf8_lossy_fills_to_the_brim() {
+        // Four valid bytes into a 3-byte buffer: three of them should still land.
+        let (buf, lossy) = StrBuf::<3>::from_utf8_lossy(b"abcd");
+        assert_eq!(buf.as_str(), "abc");
+        assert!(lossy);
+    }
+
+    #[test]
+    fn char_width_wide_and_combining() {
+        assert_eq!(char_width('a', false), 1);
+        assert_eq!(char_width('世', false), 2);
+        assert_eq!(char_width('\u{0301}', false), 0); // combining acute accent
+    }
+
+    #[test]
+    fn display_width_counts_columns() {
+        assert_eq!("a世".to_owned().display_width(), 3);
+    }
+
+    #[test]
+    fn truncate_to_width_never_splits_wide_char() {
+        // "世" is two columns; with a 1-column budget it must be dropped whole.
+        let mut s = "世a".to_owned();
+        s.truncate_to_width(1);
+        assert_eq!(s, "");
+
+        let mut s = "a世".to_owned();
+        s.truncate_to_width(1);
+        assert_eq!(s, "a");
+    }
+
+    #[test]
+    fn ccursor_columns_keep_combining_marks_attached() {
+        let text = "e\u{0301}x"; // "é" as base + combining mark, then 'x'
+        let next = ccursor_next_column(text, CCursor { index: 0, prefer_next_row: false }, false);
+        assert_eq!(next.index, 2); // past base + combining mark
+        let prev = ccursor_previous_column(text, CCursor { index: 3, prefer_next_row: false }, false);
+        assert_eq!(prev.index, 2);
+    }
+
+    #[test]
+    fn replace_all_applies_in_reverse() {
+        let mut s = "a_a_a".to_owned();
+        s.replace_all("a", "bb");
+        assert_eq!(s, "bb_bb_bb");
+    }
+
+    #[test]
+    fn replace_next_returns_cursor_after_edit() {
+        let mut s = "foo foo".to_owned();
+        let cursor = s
+            .replace_next("foo", CCursor { index: 1, prefer_next_row: false }, "xx")
+            .unwrap();
+        assert_eq!(s, "foo xx");
+        assert_eq!(cursor.index, 6); // "foo xx|"
+    }
+}